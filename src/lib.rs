@@ -0,0 +1,204 @@
+//! A cross-platform library for enumerating the shared libraries loaded in
+//! the current (or another) process and the segments therein.
+//!
+//! ## Supported OSes
+//!
+//! * Linux
+
+#[macro_use]
+extern crate cfg_if;
+extern crate libc;
+
+use std::ffi::CStr;
+use std::fmt;
+use std::ops::{Add, Sub};
+
+cfg_if! {
+    if #[cfg(target_os = "linux")] {
+        pub mod linux;
+        #[doc(hidden)]
+        pub use linux as target_lib;
+    } else {
+        // Unsupported target.
+    }
+}
+
+/// Either `Continue` or `Break`, to control whether iteration over shared
+/// libraries or segments should continue or stop.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IterationControl {
+    /// Keep iterating.
+    Continue,
+    /// Stop iterating.
+    Break,
+}
+
+impl From<()> for IterationControl {
+    #[inline]
+    fn from(_: ()) -> Self {
+        IterationControl::Continue
+    }
+}
+
+macro_rules! simple_addr_type {
+    ( $name:ident ) => {
+        /// A newtype around an address.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub usize);
+
+        impl Add<usize> for $name {
+            type Output = Self;
+            #[inline]
+            fn add(self, rhs: usize) -> Self {
+                $name(self.0 + rhs)
+            }
+        }
+
+        impl Sub<usize> for $name {
+            type Output = Self;
+            #[inline]
+            fn sub(self, rhs: usize) -> Self {
+                $name(self.0 - rhs)
+            }
+        }
+    };
+}
+
+simple_addr_type!(Svma);
+simple_addr_type!(Avma);
+
+/// The bias between a library's stated virtual memory addresses and its
+/// actual virtual memory addresses, i.e. `avma == svma + bias`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bias(pub isize);
+
+/// An identifier for a shared library, used to correlate it with separately
+/// stored debug information.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SharedLibraryId {
+    /// The `NT_GNU_BUILD_ID` note emitted by the GNU linker.
+    GnuBuildId(Vec<u8>),
+    /// A synthetic identifier folded from a library's code, used when the
+    /// library has no build id note to key off of.
+    TextHash([u8; 16]),
+}
+
+impl fmt::Display for SharedLibraryId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SharedLibraryId::GnuBuildId(ref bytes) => {
+                for byte in bytes {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+            SharedLibraryId::TextHash(ref bytes) => {
+                for byte in bytes {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl SharedLibraryId {
+    /// Get this id as a canonical Breakpad/minidump "debug id", if it has one.
+    pub fn debug_id(&self) -> Option<String> {
+        match *self {
+            SharedLibraryId::GnuBuildId(ref bytes) => Some(debug_id_from_build_id(bytes)),
+            SharedLibraryId::TextHash(_) => None,
+        }
+    }
+}
+
+fn debug_id_from_build_id(build_id: &[u8]) -> String {
+    let mut guid = [0u8; 16];
+    let len = ::std::cmp::min(build_id.len(), guid.len());
+    guid[..len].copy_from_slice(&build_id[..len]);
+
+    let data1 = guid[0] as u32
+        | (guid[1] as u32) << 8
+        | (guid[2] as u32) << 16
+        | (guid[3] as u32) << 24;
+    let data2 = guid[4] as u16 | (guid[5] as u16) << 8;
+    let data3 = guid[6] as u16 | (guid[7] as u16) << 8;
+
+    let mut id = format!("{:08X}{:04X}{:04X}", data1, data2, data3);
+    for byte in &guid[8..16] {
+        id.push_str(&format!("{:02X}", byte));
+    }
+
+    // ELF build ids have no PDB-style "age"; Breakpad convention is 0.
+    id.push('0');
+
+    id
+}
+
+/// A trait representing a shared library that is loaded in this (or some
+/// other) process.
+pub trait SharedLibrary: Sized + fmt::Debug {
+    /// The associated segment type for this shared library.
+    type Segment: Segment<SharedLibrary = Self>;
+
+    /// The associated iterator over this shared library's segments.
+    type SegmentIter: Iterator<Item = Self::Segment>;
+
+    /// Get this shared library's name.
+    fn name(&self) -> &CStr;
+
+    /// Get this shared library's identifier, if it has one.
+    fn id(&self) -> Option<SharedLibraryId>;
+
+    /// Iterate over this shared library's segments.
+    fn segments(&self) -> Self::SegmentIter;
+
+    /// Get the bias between this shared library's stated and actual virtual
+    /// memory addresses.
+    fn virtual_memory_bias(&self) -> Bias;
+
+    /// Find all shared libraries in the current process and invoke `f` on
+    /// each one.
+    fn each<F, C>(f: F)
+    where
+        F: FnMut(&Self) -> C,
+        C: Into<IterationControl>;
+}
+
+/// A trait representing a single segment of a shared library.
+pub trait Segment {
+    /// The associated shared library type for this segment.
+    type SharedLibrary: SharedLibrary;
+
+    /// Get this segment's name.
+    fn name(&self) -> &CStr;
+
+    /// Is this a code segment?
+    fn is_code(&self) -> bool;
+
+    /// Get this segment's stated virtual memory address.
+    fn stated_virtual_memory_address(&self) -> Svma;
+
+    /// Get this segment's length in memory.
+    fn len(&self) -> usize;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SharedLibraryId;
+
+    #[test]
+    fn debug_id_formats_as_guid_plus_age() {
+        let id = SharedLibraryId::GnuBuildId(vec![
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+            0x0f, 0x10,
+        ]);
+        assert_eq!(id.debug_id().unwrap(), "0403020106050807090A0B0C0D0E0F100");
+    }
+
+    #[test]
+    fn debug_id_zero_pads_short_build_ids() {
+        let id = SharedLibraryId::GnuBuildId(vec![0xab, 0xcd]);
+        assert_eq!(id.debug_id().unwrap(), "0000CDAB0000000000000000000000000");
+    }
+}
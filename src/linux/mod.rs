@@ -6,14 +6,19 @@ use super::SharedLibrary as SharedLibraryTrait;
 
 use std::any::Any;
 use std::ffi::{CStr, CString};
-use std::os::unix::ffi::{OsStringExt};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
 use std::borrow::Cow;
 use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
 use std::mem;
 use std::env::current_exe;
 use std::isize;
 use std::marker::PhantomData;
 use std::panic;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::rc::Rc;
 use std::slice;
 
 use libc;
@@ -21,13 +26,94 @@ use libc;
 cfg_if! {
     if #[cfg(target_pointer_width = "32")] {
         type Phdr = libc::Elf32_Phdr;
+        type Ehdr = libc::Elf32_Ehdr;
     } else if #[cfg(target_pointer_width = "64")] {
         type Phdr = libc::Elf64_Phdr;
+        type Ehdr = libc::Elf64_Ehdr;
     } else {
         // Unsupported.
     }
 }
 
+/// Where the bytes backing a `SharedLibrary`'s headers come from: our own
+/// live process, another process by PID, or an in-memory ELF buffer.
+enum MemorySource<'a> {
+    CurrentProcess,
+    Pid(libc::pid_t),
+    Buffer(&'a [u8]),
+}
+
+impl<'a> MemorySource<'a> {
+    fn read_at(&self, addr: usize, buf: &mut [u8]) -> io::Result<()> {
+        match *self {
+            MemorySource::CurrentProcess => {
+                unsafe {
+                    ptr::copy_nonoverlapping(addr as *const u8, buf.as_mut_ptr(), buf.len());
+                }
+                Ok(())
+            }
+            MemorySource::Pid(pid) => read_process_memory(pid, addr, buf),
+            MemorySource::Buffer(bytes) => read_buffer_at(bytes, addr, buf),
+        }
+    }
+
+    /// Read `buf.len()` bytes at a `p_offset`-relative file offset: for live
+    /// sources this is translated to a virtual address via `phdr` and
+    /// `bias` first, while for `Buffer` it indexes straight into the bytes.
+    fn read_at_file_offset(
+        &self,
+        bias: isize,
+        phdr: &Phdr,
+        file_offset: usize,
+        buf: &mut [u8],
+    ) -> io::Result<()> {
+        match *self {
+            MemorySource::Buffer(bytes) => read_buffer_at(bytes, file_offset, buf),
+            MemorySource::CurrentProcess | MemorySource::Pid(_) => {
+                let rel = file_offset as isize - phdr.p_offset as isize;
+                let addr = bias + phdr.p_vaddr as isize + rel;
+                self.read_at(addr as usize, buf)
+            }
+        }
+    }
+}
+
+fn read_buffer_at(bytes: &[u8], offset: usize, buf: &mut [u8]) -> io::Result<()> {
+    let end = offset
+        .checked_add(buf.len())
+        .filter(|&end| end <= bytes.len())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "read past end of buffer"))?;
+    buf.copy_from_slice(&bytes[offset..end]);
+    Ok(())
+}
+
+/// Read `buf.len()` bytes out of `pid`'s address space starting at `addr`.
+///
+/// This is used instead of `ptrace` so that enumerating another process's
+/// shared libraries doesn't require stopping it. `process_vm_readv` is tried
+/// first since it is a single syscall; if it is unavailable (e.g. blocked by
+/// a sandbox, or an old kernel) we fall back to seeking within
+/// `/proc/<pid>/mem`.
+fn read_process_memory(pid: libc::pid_t, addr: usize, buf: &mut [u8]) -> io::Result<()> {
+    let local_iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut _,
+        iov_len: buf.len(),
+    };
+    let remote_iov = libc::iovec {
+        iov_base: addr as *mut _,
+        iov_len: buf.len(),
+    };
+
+    let ret = unsafe { libc::process_vm_readv(pid, &local_iov, 1, &remote_iov, 1, 0) };
+    if ret == buf.len() as isize {
+        return Ok(());
+    }
+
+    let mut mem = File::open(format!("/proc/{}/mem", pid))?;
+    mem.seek(SeekFrom::Start(addr as u64))?;
+    mem.read_exact(buf)
+}
+
 const NT_GNU_BUILD_ID: u32 = 3;
 
 struct Nhdr32 {
@@ -37,12 +123,22 @@ struct Nhdr32 {
 }
 
 /// A mapped segment in an ELF file.
-#[derive(Debug)]
 pub struct Segment<'a> {
     phdr: *const Phdr,
+    // Keeps an owned header buffer (see `Headers`) alive past `phdr`'s use.
+    #[allow(dead_code)]
+    owner: Option<Rc<[Phdr]>>,
     shlib: PhantomData<&'a ::linux::SharedLibrary<'a>>,
 }
 
+impl<'a> fmt::Debug for Segment<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Segment")
+            .field("phdr", &DebugPhdr(unsafe { self.phdr.as_ref().unwrap() }))
+            .finish()
+    }
+}
+
 impl<'a> SegmentTrait for Segment<'a> {
     type SharedLibrary = ::linux::SharedLibrary<'a>;
 
@@ -95,8 +191,15 @@ impl<'a> SegmentTrait for Segment<'a> {
 }
 
 /// An iterator of mapped segments in a shared library.
+///
+/// Walks raw pointers rather than a `slice::Iter` so it doesn't need to
+/// borrow `SharedLibrary::headers` for `'a`; `owner` keeps an owned header
+/// buffer (see `Headers`) alive instead, when there is one.
 pub struct SegmentIter<'a> {
-    inner: ::std::slice::Iter<'a, Phdr>,
+    ptr: *const Phdr,
+    end: *const Phdr,
+    owner: Option<Rc<[Phdr]>>,
+    shlib: PhantomData<&'a Phdr>,
 }
 
 impl<'a> Iterator for SegmentIter<'a> {
@@ -104,8 +207,17 @@ impl<'a> Iterator for SegmentIter<'a> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|phdr| Segment {
+        if self.ptr == self.end {
+            return None;
+        }
+
+        let phdr = self.ptr;
+        unsafe {
+            self.ptr = self.ptr.offset(1);
+        }
+        Some(Segment {
             phdr: phdr,
+            owner: self.owner.clone(),
             shlib: PhantomData
         })
     }
@@ -113,9 +225,36 @@ impl<'a> Iterator for SegmentIter<'a> {
 
 impl<'a> fmt::Debug for SegmentIter<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let ref phdr = self.inner.as_slice()[0];
+        let mut debug = f.debug_struct("SegmentIter");
+        if self.ptr != self.end {
+            debug.field("phdr", &DebugPhdr(unsafe { &*self.ptr }));
+        }
+        debug.finish()
+    }
+}
+
+/// The backing storage for a `SharedLibrary`'s program headers: borrowed
+/// with no copy from this process's own live memory, or reference-counted
+/// when copied out of another process or an ELF buffer so `Segment`s can
+/// outlive the `SharedLibrary` that produced them.
+enum Headers<'a> {
+    Borrowed(&'a [Phdr]),
+    Owned(Rc<[Phdr]>),
+}
+
+impl<'a> Headers<'a> {
+    fn as_slice(&self) -> &[Phdr] {
+        match *self {
+            Headers::Borrowed(phdrs) => phdrs,
+            Headers::Owned(ref phdrs) => phdrs,
+        }
+    }
 
-        f.debug_struct("SegmentIter").field("phdr", &DebugPhdr(phdr)).finish()
+    fn owner(&self) -> Option<Rc<[Phdr]>> {
+        match *self {
+            Headers::Borrowed(_) => None,
+            Headers::Owned(ref phdrs) => Some(phdrs.clone()),
+        }
     }
 }
 
@@ -124,7 +263,8 @@ pub struct SharedLibrary<'a> {
     size: usize,
     addr: *const u8,
     name: Cow<'a, CStr>,
-    headers: &'a [Phdr],
+    headers: Headers<'a>,
+    source: MemorySource<'a>,
 }
 
 struct IterState<F> {
@@ -149,7 +289,8 @@ impl<'a> SharedLibrary<'a> {
             size: size,
             addr: info.dlpi_addr as usize as *const _,
             name,
-            headers: slice::from_raw_parts(info.dlpi_phdr, info.dlpi_phnum as usize),
+            headers: Headers::Borrowed(slice::from_raw_parts(info.dlpi_phdr, info.dlpi_phnum as usize)),
+            source: MemorySource::CurrentProcess,
         }
     }
 
@@ -176,6 +317,198 @@ impl<'a> SharedLibrary<'a> {
             }
         }
     }
+
+    /// Fold the first page of the first executable `PT_LOAD` segment into a
+    /// 16-byte identifier, the same "text hash" Breakpad falls back to when
+    /// a module has no build id.
+    fn text_hash(&self) -> Option<[u8; 16]> {
+        let segment = self.segments().find(SegmentTrait::is_code)?;
+        let phdr = unsafe { segment.phdr.as_ref().unwrap() };
+        let len = ::std::cmp::min(segment.len(), 4096);
+
+        let mut buf = vec![0u8; len];
+        self.source
+            .read_at_file_offset(self.addr as isize, phdr, phdr.p_offset as usize, &mut buf)
+            .ok()?;
+
+        let mut ident = [0u8; 16];
+        for (i, byte) in buf.iter().enumerate() {
+            ident[i % 16] ^= *byte;
+        }
+        Some(ident)
+    }
+
+    /// Parse a `SharedLibrary` out of an on-disk or mmapped ELF image.
+    /// Returns `None` if `bytes` isn't a well-formed ELF image for the
+    /// current target's word size.
+    pub fn from_elf_bytes(name: &'a CStr, bytes: &'a [u8]) -> Option<Self> {
+        let ehdr_size = mem::size_of::<Ehdr>();
+        if bytes.len() < ehdr_size {
+            return None;
+        }
+        let ehdr = unsafe { ptr::read_unaligned(bytes.as_ptr() as *const Ehdr) };
+
+        if ehdr.e_ident[libc::EI_MAG0] != libc::ELFMAG0
+            || ehdr.e_ident[libc::EI_MAG1] != libc::ELFMAG1
+            || ehdr.e_ident[libc::EI_MAG2] != libc::ELFMAG2
+            || ehdr.e_ident[libc::EI_MAG3] != libc::ELFMAG3
+        {
+            return None;
+        }
+        let want_class = if mem::size_of::<Phdr>() == mem::size_of::<libc::Elf64_Phdr>() {
+            libc::ELFCLASS64
+        } else {
+            libc::ELFCLASS32
+        };
+        if ehdr.e_ident[libc::EI_CLASS] != want_class {
+            return None;
+        }
+
+        let phentsize = ehdr.e_phentsize as usize;
+        if phentsize != mem::size_of::<Phdr>() {
+            return None;
+        }
+
+        let phnum = ehdr.e_phnum as usize;
+        let phoff = ehdr.e_phoff as usize;
+        let phdrs_len = phnum.checked_mul(phentsize)?;
+        let phdrs_end = phoff.checked_add(phdrs_len)?;
+        if phdrs_end > bytes.len() {
+            return None;
+        }
+
+        // `bytes` is a caller-supplied buffer (e.g. from a plain
+        // `fs::read()`) with no alignment guarantee, so the program headers
+        // can't be reinterpreted in place like the live, dl_iterate_phdr
+        // path reinterprets the process's own (suitably aligned) memory.
+        // Byte-copy them into an owned, properly-aligned `Vec<Phdr>`
+        // instead, the same way `read_image` does for the PID path.
+        let mut phdrs: Vec<Phdr> = Vec::with_capacity(phnum);
+        for _ in 0..phnum {
+            phdrs.push(unsafe { mem::zeroed() });
+        }
+        {
+            let phdr_bytes = unsafe {
+                slice::from_raw_parts_mut(phdrs.as_mut_ptr() as *mut u8, phdrs_len)
+            };
+            phdr_bytes.copy_from_slice(&bytes[phoff..phdrs_end]);
+        }
+
+        Some(SharedLibrary {
+            size: 0,
+            addr: ptr::null(),
+            name: Cow::Borrowed(name),
+            headers: Headers::Owned(phdrs.into()),
+            source: MemorySource::Buffer(bytes),
+        })
+    }
+}
+
+impl SharedLibrary<'static> {
+    /// Iterate over the shared libraries mapped into another process,
+    /// identified by `pid`, without ptrace-stopping it.
+    ///
+    /// This walks `/proc/<pid>/maps` to find the mapped ELF images, then
+    /// reads each image's ELF header and program headers out of the
+    /// target's address space (see `MemorySource`). The resulting
+    /// `SharedLibrary`s own their header data rather than borrowing it, so
+    /// they carry no lifetime tied to the target process.
+    pub fn each_for_pid<F, C>(pid: libc::pid_t, mut f: F)
+        where F: FnMut(&Self) -> C,
+              C: Into<IterationControl>
+    {
+        let libs = match Self::from_pid(pid) {
+            Ok(libs) => libs,
+            Err(_) => return,
+        };
+
+        for lib in libs {
+            match f(&lib).into() {
+                IterationControl::Continue => continue,
+                IterationControl::Break => break,
+            }
+        }
+    }
+
+    fn from_pid(pid: libc::pid_t) -> io::Result<Vec<Self>> {
+        let maps = fs::read_to_string(format!("/proc/{}/maps", pid))?;
+
+        // The first mapping of a file at file offset 0 is where its ELF
+        // header (which lives at vaddr 0) was loaded, so that mapping's
+        // start address is the library's load bias.
+        let mut bases: Vec<(PathBuf, usize)> = Vec::new();
+        for line in maps.lines() {
+            let mut fields = line.split_whitespace();
+            let range = match fields.next() {
+                Some(range) => range,
+                None => continue,
+            };
+            let _perms = fields.next();
+            let offset = match fields.next().and_then(|s| usize::from_str_radix(s, 16).ok()) {
+                Some(offset) => offset,
+                None => continue,
+            };
+            let _dev = fields.next();
+            let _inode = fields.next();
+            let path: String = fields.collect::<Vec<_>>().join(" ");
+
+            if offset != 0 || path.is_empty() || path.starts_with('[') {
+                continue;
+            }
+
+            let start = match range.split('-').next().and_then(|s| usize::from_str_radix(s, 16).ok()) {
+                Some(start) => start,
+                None => continue,
+            };
+
+            let path = PathBuf::from(path);
+            if !bases.iter().any(|(p, _)| *p == path) {
+                bases.push((path, start));
+            }
+        }
+
+        let source = MemorySource::Pid(pid);
+        let mut libs = Vec::with_capacity(bases.len());
+        for (path, base) in bases {
+            if let Some(lib) = Self::read_image(&source, pid, &path, base) {
+                libs.push(lib);
+            }
+        }
+        Ok(libs)
+    }
+
+    fn read_image(source: &MemorySource, pid: libc::pid_t, path: &Path, base: usize) -> Option<Self> {
+        let mut ehdr_bytes = vec![0u8; mem::size_of::<Ehdr>()];
+        source.read_at(base, &mut ehdr_bytes).ok()?;
+        let ehdr = unsafe { ptr::read_unaligned(ehdr_bytes.as_ptr() as *const Ehdr) };
+
+        let phnum = ehdr.e_phnum as usize;
+        let phentsize = ehdr.e_phentsize as usize;
+        if phnum == 0 || phentsize != mem::size_of::<Phdr>() {
+            return None;
+        }
+
+        let mut phdrs: Vec<Phdr> = Vec::with_capacity(phnum);
+        for _ in 0..phnum {
+            phdrs.push(unsafe { mem::zeroed() });
+        }
+        {
+            let phdr_bytes = unsafe {
+                slice::from_raw_parts_mut(phdrs.as_mut_ptr() as *mut u8, phnum * phentsize)
+            };
+            source.read_at(base + ehdr.e_phoff as usize, phdr_bytes).ok()?;
+        }
+
+        let name = CString::new(path.as_os_str().as_bytes()).ok()?;
+
+        Some(SharedLibrary {
+            size: 0,
+            addr: base as *const u8,
+            name: Cow::Owned(name),
+            headers: Headers::Owned(phdrs.into()),
+            source: MemorySource::Pid(pid),
+        })
+    }
 }
 
 impl<'a> SharedLibraryTrait for SharedLibrary<'a> {
@@ -195,48 +528,88 @@ impl<'a> SharedLibraryTrait for SharedLibrary<'a> {
             }
         }
 
-        unsafe {
-            for segment in self.segments() {
-                let phdr = segment.phdr.as_ref().unwrap();
-                if phdr.p_type != libc::PT_NOTE {
-                    continue;
+        for segment in self.segments() {
+            let phdr = unsafe { segment.phdr.as_ref().unwrap() };
+            if phdr.p_type != libc::PT_NOTE {
+                continue;
+            }
+
+            let mut alignment = phdr.p_align as usize;
+            // same logic as in gimli which took it from readelf
+            if alignment < 4 {
+                alignment = 4;
+            } else if alignment != 4 && alignment != 8 {
+                continue;
+            }
+
+            let mut offset = phdr.p_offset as usize;
+            let end = offset + phdr.p_filesz as usize;
+
+            while offset < end {
+                // we always use an nhdr32 here as 64bit notes have not
+                // been observed in practice.
+                let mut nhdr = Nhdr32 { n_namesz: 0, n_descsz: 0, n_type: 0 };
+                let nhdr_size = mem::size_of::<Nhdr32>();
+                {
+                    let nhdr_bytes = unsafe {
+                        slice::from_raw_parts_mut(&mut nhdr as *mut Nhdr32 as *mut u8, nhdr_size)
+                    };
+                    // A read failure here is common when reading a live
+                    // foreign process's memory (it may unmap or rewrite
+                    // pages between our /proc/<pid>/maps scan and this
+                    // read), so it shouldn't look like "definitely no id";
+                    // fall back to the text hash the same as "no PT_NOTE
+                    // found" does, rather than returning `None` outright.
+                    if self.source
+                        .read_at_file_offset(self.addr as isize, phdr, offset, nhdr_bytes)
+                        .is_err()
+                    {
+                        return self.text_hash().map(SharedLibraryId::TextHash);
+                    }
+                }
+                offset += nhdr_size;
+                let namesz = nhdr.n_namesz as usize;
+                if namesz > end.saturating_sub(offset) {
+                    return None;
                 }
+                offset += namesz;
+                align(alignment, &mut offset);
 
-                let mut alignment = phdr.p_align as usize;
-                // same logic as in gimli which took it from readelf
-                if alignment < 4 {
-                    alignment = 4;
-                } else if alignment != 4 && alignment != 8 {
-                    continue;
+                let descsz = nhdr.n_descsz as usize;
+                if descsz > end.saturating_sub(offset) {
+                    return None;
                 }
+                let mut value = vec![0u8; descsz];
+                if self.source
+                    .read_at_file_offset(self.addr as isize, phdr, offset, &mut value)
+                    .is_err()
+                {
+                    return self.text_hash().map(SharedLibraryId::TextHash);
+                }
+                offset += descsz;
+                align(alignment, &mut offset);
 
-                let mut offset = phdr.p_offset as usize;
-                let end = offset + phdr.p_filesz as usize;
-
-                while offset < end {
-                    // we always use an nhdr32 here as 64bit notes have not
-                    // been observed in practice.
-                    let nhdr = &*((self.addr as usize + offset) as *const Nhdr32);
-                    offset += mem::size_of_val(nhdr);
-                    offset += nhdr.n_namesz as usize;
-                    align(alignment, &mut offset);
-                    let value = slice::from_raw_parts(self.addr.add(offset), nhdr.n_descsz as usize);
-                    offset += nhdr.n_descsz as usize;
-                    align(alignment, &mut offset);
-
-                    if nhdr.n_type as u32 == NT_GNU_BUILD_ID {
-                        return Some(SharedLibraryId::GnuBuildId(value.to_vec()));
-                    }
+                if nhdr.n_type as u32 == NT_GNU_BUILD_ID {
+                    return Some(SharedLibraryId::GnuBuildId(value));
                 }
             }
         }
 
-        None
+        // No NT_GNU_BUILD_ID note; many stripped or vendor libraries lack
+        // one. Fall back to a synthesized Breakpad-style text hash so the
+        // library can still be correlated across runs.
+        self.text_hash().map(SharedLibraryId::TextHash)
     }
 
     #[inline]
     fn segments(&self) -> Self::SegmentIter {
-        SegmentIter { inner: self.headers.iter() }
+        let headers = self.headers.as_slice();
+        SegmentIter {
+            ptr: headers.as_ptr(),
+            end: unsafe { headers.as_ptr().add(headers.len()) },
+            owner: self.headers.owner(),
+            shlib: PhantomData,
+        }
     }
 
     #[inline]
@@ -272,12 +645,13 @@ impl<'a> fmt::Debug for SharedLibrary<'a> {
 
         // Debug does not usually have a trailing comma in the list,
         // last element must be formatted separately.
-        let l = self.headers.len();
-        self.headers[..(l - 1)].into_iter()
-            .map(|phdr| write!(f, "{:?}, ", &DebugPhdr(phdr)))
-            .collect::<fmt::Result>()?;
-
-        write!(f, "{:?}", &DebugPhdr(&self.headers[l - 1]))?;
+        let headers = self.headers.as_slice();
+        if let Some((last, rest)) = headers.split_last() {
+            for phdr in rest {
+                write!(f, "{:?}, ", &DebugPhdr(phdr))?;
+            }
+            write!(f, "{:?}", &DebugPhdr(last))?;
+        }
 
         write!(f, "] }}")
     }
@@ -391,6 +765,107 @@ mod tests {
         panic!();
     }
 
+    #[test]
+    fn id_falls_back_to_text_hash() {
+        use super::super::SharedLibraryId;
+
+        linux::SharedLibrary::each(|shlib| {
+            match shlib.id() {
+                Some(SharedLibraryId::GnuBuildId(_)) => {}
+                Some(SharedLibraryId::TextHash(hash)) => {
+                    assert_ne!(hash, [0u8; 16]);
+                }
+                None => panic!("expected a build id or a text hash fallback"),
+            }
+        });
+    }
+
+    #[test]
+    fn from_elf_bytes_parses_a_buffer() {
+        use std::ffi::CString;
+        use std::mem;
+        use std::slice;
+        use libc;
+        use super::super::SharedLibraryId;
+
+        let ehdr_size = mem::size_of::<super::Ehdr>();
+        let phdr_size = mem::size_of::<super::Phdr>();
+        let code = b"some executable bytes to hash";
+
+        let phoff = ehdr_size;
+        let code_off = phoff + phdr_size;
+
+        let mut ehdr: super::Ehdr = unsafe { mem::zeroed() };
+        ehdr.e_ident[libc::EI_MAG0] = libc::ELFMAG0;
+        ehdr.e_ident[libc::EI_MAG1] = libc::ELFMAG1;
+        ehdr.e_ident[libc::EI_MAG2] = libc::ELFMAG2;
+        ehdr.e_ident[libc::EI_MAG3] = libc::ELFMAG3;
+        ehdr.e_ident[libc::EI_CLASS] = if phdr_size == mem::size_of::<libc::Elf64_Phdr>() {
+            libc::ELFCLASS64
+        } else {
+            libc::ELFCLASS32
+        };
+        ehdr.e_phoff = phoff as _;
+        ehdr.e_phnum = 1;
+        ehdr.e_phentsize = phdr_size as u16;
+
+        let mut phdr: super::Phdr = unsafe { mem::zeroed() };
+        phdr.p_type = libc::PT_LOAD;
+        phdr.p_flags = 0x1; // PF_X
+        phdr.p_offset = code_off as _;
+        phdr.p_vaddr = code_off as _;
+        phdr.p_filesz = code.len() as _;
+        phdr.p_memsz = code.len() as _;
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(unsafe {
+            slice::from_raw_parts(&ehdr as *const _ as *const u8, ehdr_size)
+        });
+        bytes.extend_from_slice(unsafe {
+            slice::from_raw_parts(&phdr as *const _ as *const u8, phdr_size)
+        });
+        bytes.extend_from_slice(code);
+
+        let name = CString::new("/tmp/fake.so").unwrap();
+        let shlib = linux::SharedLibrary::from_elf_bytes(&name, &bytes).unwrap();
+
+        let mut found_load = false;
+        for seg in shlib.segments() {
+            found_load |= seg.is_code();
+        }
+        assert!(found_load);
+
+        match shlib.id() {
+            Some(SharedLibraryId::TextHash(_)) => {}
+            other => panic!("expected a text hash fallback, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_elf_bytes_rejects_non_elf_buffer() {
+        use std::ffi::CString;
+        use std::mem;
+
+        let bytes = vec![0u8; mem::size_of::<super::Ehdr>()];
+        let name = CString::new("/tmp/not-elf").unwrap();
+        assert!(linux::SharedLibrary::from_elf_bytes(&name, &bytes).is_none());
+    }
+
+    #[test]
+    fn each_for_pid_self() {
+        let pid = ::std::process::id() as ::libc::pid_t;
+
+        let mut found_libc = false;
+        linux::SharedLibrary::each_for_pid(pid, |shlib| {
+            found_libc |= shlib.name()
+                .to_bytes()
+                .split(|c| *c == b'.' || *c == b'/')
+                .find(|s| s == b"libc")
+                .is_some();
+        });
+        assert!(found_libc);
+    }
+
     #[test]
     fn have_load_segment() {
         linux::SharedLibrary::each(|shlib| {